@@ -1,3 +1,4 @@
+use std::collections::{BTreeMap, BTreeSet};
 use std::fs::File;
 use std::io::{BufRead, BufReader};
 use std::path::Path;
@@ -6,8 +7,9 @@ use std::thread;
 use std::time::{Duration, Instant};
 
 use anyhow::{bail, Context, Result};
-use clap::Parser;
+use clap::{Parser, ValueEnum};
 use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
+use serde::Serialize;
 use threadpool::ThreadPool;
 use usi::{
     CheckmateParams, EngineCommand, GuiCommand, InfoParams, MateParam, ThinkParams,
@@ -27,6 +29,36 @@ struct EngineOptions {
     hash: usize,
 }
 
+/// Limits applied to each mate search
+#[derive(Parser, Debug, Clone, Copy)]
+#[command()]
+struct SearchLimits {
+    /// The timeout for each mate search, in milliseconds. Use 0 to search without a timeout,
+    /// bounded only by `--file-timeout-secs` (if set).
+    #[arg(long, default_value = "30000")]
+    mate_timeout_ms: u64,
+
+    /// The total wall-clock budget for a single sfen file, in seconds. Unset means unbounded.
+    #[arg(long)]
+    file_timeout_secs: Option<u64>,
+}
+
+impl SearchLimits {
+    /// The per-position mate search timeout, or `None` for an infinite search
+    fn mate_timeout(&self) -> Option<Duration> {
+        if self.mate_timeout_ms == 0 {
+            None
+        } else {
+            Some(Duration::from_millis(self.mate_timeout_ms))
+        }
+    }
+
+    /// The per-file wall-clock budget, or `None` if unbounded
+    fn file_timeout(&self) -> Option<Duration> {
+        self.file_timeout_secs.map(Duration::from_secs)
+    }
+}
+
 /// A benchmarking tool for mate engines
 #[derive(Parser, Debug, Clone)]
 #[command(version, disable_help_flag = true)]
@@ -47,11 +79,116 @@ struct Args {
     #[command(flatten)]
     engine_options: EngineOptions,
 
+    /// The search limits
+    #[command(flatten)]
+    search_limits: SearchLimits,
+
+    /// Additional USI options to forward to the engine as `setoption`, in the form
+    /// `name=value`. May be given multiple times. These are applied after the
+    /// built-in defaults, so they can override them by name.
+    #[arg(long = "option", value_parser = parse_option)]
+    options: Vec<(String, String)>,
+
+    /// The format to report results in
+    #[arg(long, value_enum, default_value = "text")]
+    format: OutputFormat,
+
+    /// The path to a baseline engine executable. When set, each sfen file is solved with
+    /// both the baseline and `--engine-path`, and a regression report comparing the two is
+    /// printed instead of the usual per-engine statistics.
+    #[arg(long)]
+    baseline_engine_path: Option<String>,
+
+    /// Comma-separated list of thread counts to sweep, e.g. `1,2,4,8`. When set, each sfen
+    /// file is solved once per thread count and a speedup table (nps at N threads ÷ nps at
+    /// the first listed thread count) is printed instead of the usual statistics.
+    /// `--threads` is ignored in this mode: each sweep entry overrides it in turn.
+    #[arg(long, value_delimiter = ',')]
+    thread_sweep: Vec<usize>,
+
     /// Show help message
     #[clap(long, action = clap::ArgAction::HelpLong)]
     help: Option<bool>,
 }
 
+/// The format to report solve results in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum OutputFormat {
+    /// Human-readable text, as printed to stdout while solving
+    Text,
+    /// A single JSON document with per-file reports and an aggregate summary
+    Json,
+    /// One CSV row per sfen file, plus a totals row
+    Csv,
+}
+
+/// Parse a `name=value` USI option string
+fn parse_option(s: &str) -> Result<(String, String), String> {
+    let (name, value) = s
+        .split_once('=')
+        .ok_or_else(|| format!("invalid option `{s}`: expected `name=value`"))?;
+
+    Ok((name.to_string(), value.to_string()))
+}
+
+/// The expected answer for an sfen position, parsed from the text following a `;` delimiter
+#[derive(Debug, Clone, PartialEq)]
+enum Expected {
+    /// A mate is expected, optionally with the expected ply count and/or principal variation
+    Mate {
+        ply: Option<usize>,
+        pv: Option<Vec<String>>,
+    },
+    /// No mate is expected
+    NoMate,
+}
+
+/// An sfen line together with its optional expected answer
+#[derive(Debug, Clone)]
+struct SfenEntry {
+    /// The sfen position, with the expected-answer suffix stripped off
+    sfen: String,
+    /// The expected answer, if the line carries one
+    expected: Option<Expected>,
+}
+
+/// Parse one line of an sfen file
+///
+/// Each line is an sfen position, optionally followed by an expected answer after a `;`
+/// delimiter, e.g. `<sfen> ; mate 7` or `<sfen> ; nomate`. A `mate` answer may additionally
+/// carry the expected principal variation as a space-separated list of moves, e.g.
+/// `<sfen> ; mate 7 7g7f 3c3d ...`.
+fn parse_sfen_line(line: &str) -> Result<SfenEntry> {
+    let Some((sfen, answer)) = line.split_once(';') else {
+        return Ok(SfenEntry {
+            sfen: line.trim().to_string(),
+            expected: None,
+        });
+    };
+
+    let mut tokens = answer.split_whitespace();
+    let expected = match tokens.next() {
+        Some("mate") => {
+            let ply = tokens
+                .next()
+                .map(|s| s.parse::<usize>())
+                .transpose()
+                .context("Invalid expected mate ply")?;
+            let pv: Vec<String> = tokens.map(|s| s.to_string()).collect();
+            let pv = if pv.is_empty() { None } else { Some(pv) };
+            Some(Expected::Mate { ply, pv })
+        }
+        Some("nomate") => Some(Expected::NoMate),
+        Some(other) => bail!("Invalid expected answer `{other}`"),
+        None => None,
+    };
+
+    Ok(SfenEntry {
+        sfen: sfen.trim().to_string(),
+        expected,
+    })
+}
+
 /// Statistics for a solve
 #[derive(Debug, Default, Clone)]
 struct SolveStats {
@@ -72,22 +209,62 @@ struct SolveStats {
 
     /// The indices of the positions with an error or no mate
     error_or_nomate_indices: Vec<usize>,
+
+    /// The number of positions that matched their expected answer
+    num_correct: usize,
+    /// The number of positions that did not match their expected answer
+    num_wrong: usize,
+    /// The indices of the positions that did not match their expected answer
+    wrong_indices: Vec<usize>,
+
+    /// The number of positions where the search timed out before concluding a result
+    num_timeout: usize,
+    /// The indices of the positions where the search timed out
+    timeout_indices: Vec<usize>,
 }
 
 impl SolveStats {
-    fn update_by_checkmate(&mut self, mate: &CheckmateParams) {
+    fn update_by_checkmate(&mut self, mate: &CheckmateParams, expected: Option<&Expected>) {
         use CheckmateParams::*;
 
         let sfen_index = self.num_sfens;
         self.num_sfens += 1;
         self.nodes += self.last_nodes;
         self.last_nodes = 0;
+
+        let is_correct = match (mate, expected) {
+            (_, None) => None,
+            (Mate(moves), Some(Expected::Mate { ply, pv })) => {
+                let ply_matches = ply.map_or(true, |ply| moves.len() == ply);
+                let pv_matches = pv.as_ref().map_or(true, |pv| {
+                    moves.iter().map(|m| m.to_string()).eq(pv.iter().cloned())
+                });
+                Some(ply_matches && pv_matches)
+            }
+            (Mate(_), Some(Expected::NoMate)) => Some(false),
+            (NoMate, Some(Expected::NoMate)) => Some(true),
+            (_, Some(_)) => Some(false),
+        };
+        match is_correct {
+            Some(true) => self.num_correct += 1,
+            Some(false) => {
+                self.num_wrong += 1;
+                self.wrong_indices.push(sfen_index);
+            }
+            None => {}
+        }
+
         match mate {
             Mate(_) => self.num_mate += 1,
             NoMate => {
                 self.num_nomate += 1;
                 self.error_or_nomate_indices.push(sfen_index);
             }
+            Timeout => {
+                self.num_timeout += 1;
+                self.timeout_indices.push(sfen_index);
+                self.error_or_nomate_indices.push(sfen_index);
+            }
             _ => {
                 self.num_errors += 1;
                 self.error_or_nomate_indices.push(sfen_index);
@@ -95,6 +272,18 @@ impl SolveStats {
         }
     }
 
+    /// Mark any positions up to `num_sfens` that have not been processed yet as timed out,
+    /// e.g. because the per-file wall-clock budget elapsed before the engine finished them
+    fn fill_remaining_as_timeout(&mut self, num_sfens: usize) {
+        while self.num_sfens < num_sfens {
+            let sfen_index = self.num_sfens;
+            self.num_sfens += 1;
+            self.num_timeout += 1;
+            self.timeout_indices.push(sfen_index);
+            self.error_or_nomate_indices.push(sfen_index);
+        }
+    }
+
     fn update_by_info(&mut self, info: &[InfoParams]) {
         let has_pv = info.iter().any(|x| matches!(x, InfoParams::Pv(_)));
         if has_pv {
@@ -123,6 +312,10 @@ fn check_args(args: &Args) -> Result<()> {
         bail!("Threads must be greater than 0");
     }
 
+    if args.thread_sweep.iter().any(|&threads| threads == 0) {
+        bail!("--thread-sweep entries must be greater than 0");
+    }
+
     Ok(())
 }
 
@@ -132,6 +325,7 @@ fn check_args(args: &Args) -> Result<()> {
 fn initialize_engine(
     engine_path: &str,
     engine_options: &EngineOptions,
+    options: &[(String, String)],
 ) -> Result<UsiEngineHandler> {
     let mut engine = UsiEngineHandler::spawn(&engine_path, ".").context("Engine spawn error")?;
 
@@ -157,16 +351,27 @@ fn initialize_engine(
         ))?;
     }
 
+    for (name, value) in options {
+        engine.send_command(&GuiCommand::SetOption(name.clone(), Some(value.clone())))?;
+    }
+
     engine.prepare()?;
     engine.send_command(&GuiCommand::UsiNewGame)?;
     Ok(engine)
 }
 
 /// Start searching
-fn start_searching(engine: &mut UsiEngineHandler, sfen: &str) -> Result<()> {
+fn start_searching(
+    engine: &mut UsiEngineHandler,
+    sfen: &str,
+    mate_timeout: Option<Duration>,
+) -> Result<()> {
     let setpos_cmd = GuiCommand::Position(sfen.to_string());
-    let mate_cmd =
-        GuiCommand::Go(ThinkParams::new().mate(MateParam::Timeout(Duration::from_secs(30))));
+    let mate_param = match mate_timeout {
+        Some(timeout) => MateParam::Timeout(timeout),
+        None => MateParam::Infinite,
+    };
+    let mate_cmd = GuiCommand::Go(ThinkParams::new().mate(mate_param));
 
     engine.send_command(&setpos_cmd)?;
     engine.send_command(&mate_cmd)?;
@@ -190,11 +395,18 @@ fn get_style() -> Result<ProgressStyle> {
 fn solve<'a>(
     engine_path: &str,
     engine_options: &EngineOptions,
+    search_limits: &SearchLimits,
+    options: &[(String, String)],
     sfen_path: &str,
     progress: &MultiProgress,
 ) -> Result<SolveStats> {
+    let entries = BufReader::new(File::open(sfen_path)?)
+        .lines()
+        .map(|line| parse_sfen_line(&line?))
+        .collect::<Result<Vec<_>>>()?;
+
     // <progress_bar> prepare progress bar
-    let num_sfens = BufReader::new(File::open(sfen_path)?).lines().count();
+    let num_sfens = entries.len();
     let progress_bar = progress.add(ProgressBar::new(num_sfens as u64));
     let sfen_file_name = Path::new(sfen_path)
         .file_name()
@@ -204,8 +416,9 @@ fn solve<'a>(
     let progress_bar = Arc::new(progress_bar);
     // </progress_bar>
 
-    let mut engine = initialize_engine(engine_path, engine_options)?;
+    let mut engine = initialize_engine(engine_path, engine_options, options)?;
 
+    let expected: Vec<Option<Expected>> = entries.iter().map(|e| e.expected.clone()).collect();
     let solve_stats = Arc::new(Mutex::new(SolveStats::default()));
     let solve_stats_clone = solve_stats.clone();
     let progress_bar_clone = progress_bar.clone();
@@ -217,7 +430,8 @@ fn solve<'a>(
                 progress_bar_clone.inc(1);
 
                 let mut solve_stats = solve_stats_clone.lock().unwrap();
-                solve_stats.update_by_checkmate(mate)
+                let expected = expected.get(solve_stats.num_sfens).and_then(|e| e.as_ref());
+                solve_stats.update_by_checkmate(mate, expected)
             }
             Some(Info(info)) => {
                 let mut solve_stats = solve_stats_clone.lock().unwrap();
@@ -228,13 +442,26 @@ fn solve<'a>(
         Ok(())
     })?;
 
+    let mate_timeout = search_limits.mate_timeout();
+    let file_timeout = search_limits.file_timeout();
+
     let start_instant = Instant::now();
-    for sfen in BufReader::new(File::open(sfen_path)?).lines() {
-        start_searching(&mut engine, &sfen?)?;
+    for entry in &entries {
+        start_searching(&mut engine, &entry.sfen, mate_timeout)?;
     }
 
-    // wait until all sfens are processed
-    while solve_stats.lock().unwrap().num_sfens < num_sfens {
+    // wait until all sfens are processed, or the per-file wall-clock budget elapses
+    loop {
+        if solve_stats.lock().unwrap().num_sfens >= num_sfens {
+            break;
+        }
+        if file_timeout.is_some_and(|timeout| start_instant.elapsed() > timeout) {
+            solve_stats
+                .lock()
+                .unwrap()
+                .fill_remaining_as_timeout(num_sfens);
+            break;
+        }
         thread::sleep(Duration::from_millis(100));
     }
     let end_instant = Instant::now();
@@ -246,6 +473,25 @@ fn solve<'a>(
     Ok(solve_stats)
 }
 
+/// Format a list of indices, truncating to the first 10 entries
+fn format_indices(indices: &[usize]) -> String {
+    let mut indices = indices.to_vec();
+    let is_too_many_indices = indices.len() > 10;
+    if is_too_many_indices {
+        indices.truncate(10);
+    }
+    let mut indices = indices
+        .iter()
+        .map(|i| i.to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+    if is_too_many_indices {
+        indices.push_str(", ...");
+    }
+
+    indices
+}
+
 /// Print the statistics
 fn print_stats(sfen_path: &str, solve_stats: &SolveStats) {
     println!(
@@ -262,30 +508,437 @@ fn print_stats(sfen_path: &str, solve_stats: &SolveStats) {
     if solve_stats.num_errors > 0 {
         println!("  Errors: {}", solve_stats.num_errors);
     }
+    if solve_stats.num_timeout > 0 {
+        println!("  Timeouts: {}", solve_stats.num_timeout);
+    }
+    if !solve_stats.timeout_indices.is_empty() {
+        println!(
+            "  Timeout indices: {}",
+            format_indices(&solve_stats.timeout_indices)
+        );
+    }
     if !solve_stats.error_or_nomate_indices.is_empty() {
-        // take first 10 element
-        let mut error_or_nomate_indices = solve_stats.error_or_nomate_indices.clone();
-        let is_too_many_indices = error_or_nomate_indices.len() > 10;
-        if is_too_many_indices {
-            error_or_nomate_indices.truncate(10);
+        println!(
+            "  Error or Nomate indices: {}",
+            format_indices(&solve_stats.error_or_nomate_indices),
+        );
+    }
+    if solve_stats.num_correct > 0 || solve_stats.num_wrong > 0 {
+        println!(
+            "  Correct: {}, Wrong: {}",
+            solve_stats.num_correct, solve_stats.num_wrong
+        );
+    }
+    if !solve_stats.wrong_indices.is_empty() {
+        println!(
+            "  Wrong indices: {}",
+            format_indices(&solve_stats.wrong_indices)
+        );
+    }
+}
+
+/// A machine-readable report of the solve results for a single sfen file
+#[derive(Debug, Serialize)]
+struct FileReport {
+    sfen_path: String,
+    elapsed_secs: f64,
+    nodes: usize,
+    nps: f64,
+    num_sfens: usize,
+    num_mate: usize,
+    num_nomate: usize,
+    num_errors: usize,
+    num_correct: usize,
+    num_wrong: usize,
+    num_timeout: usize,
+    error_or_nomate_indices: Vec<usize>,
+    wrong_indices: Vec<usize>,
+    timeout_indices: Vec<usize>,
+}
+
+impl FileReport {
+    fn new(sfen_path: &str, solve_stats: &SolveStats) -> Self {
+        let elapsed_secs = solve_stats.elapsed.as_secs_f64();
+        Self {
+            sfen_path: sfen_path.to_string(),
+            elapsed_secs,
+            nodes: solve_stats.nodes,
+            nps: solve_stats.nodes as f64 / elapsed_secs,
+            num_sfens: solve_stats.num_sfens,
+            num_mate: solve_stats.num_mate,
+            num_nomate: solve_stats.num_nomate,
+            num_errors: solve_stats.num_errors,
+            num_correct: solve_stats.num_correct,
+            num_wrong: solve_stats.num_wrong,
+            num_timeout: solve_stats.num_timeout,
+            error_or_nomate_indices: solve_stats.error_or_nomate_indices.clone(),
+            wrong_indices: solve_stats.wrong_indices.clone(),
+            timeout_indices: solve_stats.timeout_indices.clone(),
         }
-        let mut error_or_nomate_indices = error_or_nomate_indices
+    }
+}
+
+/// The aggregate summary across all sfen files in a report
+#[derive(Debug, Serialize)]
+struct Summary {
+    elapsed_secs: f64,
+    nodes: usize,
+    nps: f64,
+    num_sfens: usize,
+    num_mate: usize,
+    num_nomate: usize,
+    num_errors: usize,
+    num_correct: usize,
+    num_wrong: usize,
+    num_timeout: usize,
+}
+
+impl Summary {
+    fn new(files: &[FileReport]) -> Self {
+        let elapsed_secs = files.iter().map(|f| f.elapsed_secs).sum();
+        let nodes = files.iter().map(|f| f.nodes).sum();
+        Self {
+            elapsed_secs,
+            nodes,
+            nps: nodes as f64 / elapsed_secs,
+            num_sfens: files.iter().map(|f| f.num_sfens).sum(),
+            num_mate: files.iter().map(|f| f.num_mate).sum(),
+            num_nomate: files.iter().map(|f| f.num_nomate).sum(),
+            num_errors: files.iter().map(|f| f.num_errors).sum(),
+            num_correct: files.iter().map(|f| f.num_correct).sum(),
+            num_wrong: files.iter().map(|f| f.num_wrong).sum(),
+            num_timeout: files.iter().map(|f| f.num_timeout).sum(),
+        }
+    }
+}
+
+/// A machine-readable report of a full benchmark run
+#[derive(Debug, Serialize)]
+struct Report {
+    files: Vec<FileReport>,
+    summary: Summary,
+}
+
+impl Report {
+    fn new(results: &[(String, SolveStats)]) -> Self {
+        let files = results
             .iter()
-            .map(|i| i.to_string())
-            .collect::<Vec<_>>()
-            .join(", ");
-        if is_too_many_indices {
-            error_or_nomate_indices.push_str(", ...");
+            .map(|(sfen_path, solve_stats)| FileReport::new(sfen_path, solve_stats))
+            .collect::<Vec<_>>();
+        let summary = Summary::new(&files);
+        Self { files, summary }
+    }
+}
+
+/// Print a report as a single pretty-printed JSON document
+fn print_report_json(report: &Report) -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(report)?);
+    Ok(())
+}
+
+/// Print a report as CSV: one row per sfen file, plus a totals row
+fn print_report_csv(report: &Report) {
+    println!(
+        "sfen_path,elapsed_secs,nodes,nps,num_sfens,num_mate,num_nomate,num_errors,num_correct,num_wrong,num_timeout"
+    );
+    for file in &report.files {
+        println!(
+            "{},{:.3},{},{:.2},{},{},{},{},{},{},{}",
+            file.sfen_path,
+            file.elapsed_secs,
+            file.nodes,
+            file.nps,
+            file.num_sfens,
+            file.num_mate,
+            file.num_nomate,
+            file.num_errors,
+            file.num_correct,
+            file.num_wrong,
+            file.num_timeout,
+        );
+    }
+    let summary = &report.summary;
+    println!(
+        "TOTAL,{:.3},{},{:.2},{},{},{},{},{},{},{}",
+        summary.elapsed_secs,
+        summary.nodes,
+        summary.nps,
+        summary.num_sfens,
+        summary.num_mate,
+        summary.num_nomate,
+        summary.num_errors,
+        summary.num_correct,
+        summary.num_wrong,
+        summary.num_timeout,
+    );
+}
+
+/// The indices of the positions a `SolveStats` reported as a mate
+fn mate_indices(solve_stats: &SolveStats) -> BTreeSet<usize> {
+    let error_or_nomate: BTreeSet<usize> = solve_stats
+        .error_or_nomate_indices
+        .iter()
+        .copied()
+        .collect();
+    (0..solve_stats.num_sfens)
+        .filter(|i| !error_or_nomate.contains(i))
+        .collect()
+}
+
+/// A comparison between a baseline and a candidate engine's `SolveStats` for one sfen file
+#[derive(Debug, Default, Clone)]
+struct Comparison {
+    /// Candidate nps / baseline nps
+    nps_ratio: f64,
+    /// Candidate nodes / baseline nodes
+    node_ratio: f64,
+    /// Positions the baseline solved as mate that the candidate now reports as nomate/error
+    newly_broken_indices: Vec<usize>,
+    /// Positions the baseline reported as nomate/error that the candidate now solves as mate
+    newly_fixed_indices: Vec<usize>,
+}
+
+impl Comparison {
+    fn new(baseline: &SolveStats, candidate: &SolveStats) -> Self {
+        let baseline_nps = baseline.nodes as f64 / baseline.elapsed.as_secs_f64();
+        let candidate_nps = candidate.nodes as f64 / candidate.elapsed.as_secs_f64();
+
+        let baseline_mate = mate_indices(baseline);
+        let candidate_mate = mate_indices(candidate);
+
+        Self {
+            nps_ratio: candidate_nps / baseline_nps,
+            node_ratio: candidate.nodes as f64 / baseline.nodes as f64,
+            newly_broken_indices: baseline_mate.difference(&candidate_mate).copied().collect(),
+            newly_fixed_indices: candidate_mate.difference(&baseline_mate).copied().collect(),
+        }
+    }
+}
+
+/// Print a diff table row for a single sfen file's comparison
+fn print_comparison(sfen_path: &str, comparison: &Comparison) {
+    println!(
+        "[{:>48}] nps ratio: {:6.2}x, node ratio: {:6.2}x",
+        sfen_path, comparison.nps_ratio, comparison.node_ratio,
+    );
+    if !comparison.newly_broken_indices.is_empty() {
+        println!(
+            "  Newly broken indices: {}",
+            format_indices(&comparison.newly_broken_indices)
+        );
+    }
+    if !comparison.newly_fixed_indices.is_empty() {
+        println!(
+            "  Newly fixed indices: {}",
+            format_indices(&comparison.newly_fixed_indices)
+        );
+    }
+}
+
+/// Run the same sfen suites through a baseline engine and the candidate engine, and print a
+/// regression report comparing the two
+fn run_comparison(args: &Args, baseline_engine_path: &str) -> Result<()> {
+    let progress = Arc::from(MultiProgress::new());
+
+    let (tx, rx) = mpsc::channel();
+    let thread_pool = ThreadPool::new(args.workers);
+    for sfen_path in &args.sfen_paths {
+        let baseline_engine_path = baseline_engine_path.to_string();
+        let engine_path = args.engine_path.clone();
+        let engine_options = args.engine_options;
+        let search_limits = args.search_limits;
+        let options = args.options.clone();
+        let sfen_path = sfen_path.clone();
+        let progress = progress.clone();
+        let tx = tx.clone();
+        thread_pool.execute(move || {
+            let result = (|| -> Result<(SolveStats, SolveStats)> {
+                let baseline_stats = solve(
+                    &baseline_engine_path,
+                    &engine_options,
+                    &search_limits,
+                    &options,
+                    &sfen_path,
+                    &progress,
+                )
+                .context("baseline engine failed")?;
+                let candidate_stats = solve(
+                    &engine_path,
+                    &engine_options,
+                    &search_limits,
+                    &options,
+                    &sfen_path,
+                    &progress,
+                )
+                .context("candidate engine failed")?;
+                Ok((baseline_stats, candidate_stats))
+            })();
+            tx.send((sfen_path, result)).unwrap();
+        });
+    }
+
+    drop(tx);
+    let mut total_baseline_nodes = 0;
+    let mut total_baseline_elapsed = Duration::default();
+    let mut total_candidate_nodes = 0;
+    let mut total_candidate_elapsed = Duration::default();
+    let mut total_newly_broken = 0;
+    let mut total_newly_fixed = 0;
+    let mut num_failed = 0;
+    for (sfen_path, result) in rx.iter() {
+        match result {
+            Ok((baseline_stats, candidate_stats)) => {
+                let comparison = Comparison::new(&baseline_stats, &candidate_stats);
+                progress.suspend(|| print_comparison(&sfen_path, &comparison));
+
+                total_baseline_nodes += baseline_stats.nodes;
+                total_baseline_elapsed += baseline_stats.elapsed;
+                total_candidate_nodes += candidate_stats.nodes;
+                total_candidate_elapsed += candidate_stats.elapsed;
+                total_newly_broken += comparison.newly_broken_indices.len();
+                total_newly_fixed += comparison.newly_fixed_indices.len();
+            }
+            Err(err) => {
+                num_failed += 1;
+                progress.suspend(|| eprintln!("[{:>48}] FAILED: {:#}", sfen_path, err));
+            }
+        }
+    }
+
+    thread_pool.join();
+    progress.clear()?;
+
+    let baseline_nps = total_baseline_nodes as f64 / total_baseline_elapsed.as_secs_f64();
+    let candidate_nps = total_candidate_nodes as f64 / total_candidate_elapsed.as_secs_f64();
+    println!(
+        "[{:>48}] nps ratio: {:6.2}x, node ratio: {:6.2}x",
+        "TOTAL",
+        candidate_nps / baseline_nps,
+        total_candidate_nodes as f64 / total_baseline_nodes as f64,
+    );
+    println!(
+        "  Newly broken: {}, Newly fixed: {}",
+        total_newly_broken, total_newly_fixed
+    );
+
+    if num_failed > 0 {
+        bail!(
+            "{} of {} sfen files failed to compare",
+            num_failed,
+            args.sfen_paths.len()
+        );
+    }
+
+    Ok(())
+}
+
+/// One thread-count measurement within a thread-scaling sweep
+#[derive(Debug, Clone, Default)]
+struct ThreadSweepPoint {
+    threads: usize,
+    solve_stats: SolveStats,
+}
+
+impl ThreadSweepPoint {
+    fn nps(&self) -> f64 {
+        self.solve_stats.nodes as f64 / self.solve_stats.elapsed.as_secs_f64()
+    }
+}
+
+/// Print a speedup table for one sfen file (or the aggregate, when `sfen_path` is `"TOTAL"`)
+fn print_thread_sweep(sfen_path: &str, points: &[ThreadSweepPoint]) {
+    println!("[{:>48}]", sfen_path);
+    let baseline_nps = points
+        .first()
+        .map(ThreadSweepPoint::nps)
+        .unwrap_or(f64::NAN);
+    for point in points {
+        println!(
+            "  threads={:<3} nps: {:10.2}  speedup: {:5.2}x",
+            point.threads,
+            point.nps(),
+            point.nps() / baseline_nps,
+        );
+    }
+}
+
+/// Solve each sfen file once per thread count in `args.thread_sweep`, and print a per-file
+/// and aggregate speedup table
+///
+/// Measurements are run serially, one engine at a time, regardless of `--workers`: running
+/// two thread counts concurrently would have them contend for the same physical cores,
+/// which corrupts the nps (and therefore speedup) numbers this mode exists to produce.
+fn run_thread_sweep(args: &Args) -> Result<()> {
+    let progress = Arc::from(MultiProgress::new());
+
+    let by_file = (|| -> Result<BTreeMap<String, Vec<ThreadSweepPoint>>> {
+        let mut by_file = BTreeMap::new();
+        for sfen_path in &args.sfen_paths {
+            let mut points = Vec::new();
+            for &threads in &args.thread_sweep {
+                let mut engine_options = args.engine_options;
+                engine_options.threads = threads;
+                let solve_stats = solve(
+                    &args.engine_path,
+                    &engine_options,
+                    &args.search_limits,
+                    &args.options,
+                    sfen_path,
+                    &progress,
+                )?;
+                points.push(ThreadSweepPoint {
+                    threads,
+                    solve_stats,
+                });
+            }
+            by_file.insert(sfen_path.clone(), points);
         }
+        Ok(by_file)
+    })();
 
-        println!("  Error or Nomate indices: {}", error_or_nomate_indices,);
+    progress.clear()?;
+    let by_file = by_file?;
+
+    let mut aggregate: Vec<ThreadSweepPoint> = args
+        .thread_sweep
+        .iter()
+        .map(|&threads| ThreadSweepPoint {
+            threads,
+            solve_stats: SolveStats::default(),
+        })
+        .collect();
+
+    for (sfen_path, points) in by_file {
+        print_thread_sweep(&sfen_path, &points);
+
+        for point in &points {
+            if let Some(agg) = aggregate
+                .iter_mut()
+                .find(|agg| agg.threads == point.threads)
+            {
+                agg.solve_stats.nodes += point.solve_stats.nodes;
+                agg.solve_stats.elapsed += point.solve_stats.elapsed;
+            }
+        }
     }
+
+    print_thread_sweep("TOTAL", &aggregate);
+
+    Ok(())
 }
 
 fn main() -> Result<()> {
     let args = Args::parse();
     check_args(&args)?;
 
+    if let Some(baseline_engine_path) = args.baseline_engine_path.clone() {
+        return run_comparison(&args, &baseline_engine_path);
+    }
+
+    if !args.thread_sweep.is_empty() {
+        return run_thread_sweep(&args);
+    }
+
     let progress = Arc::from(MultiProgress::new());
 
     let (tx, rx) = mpsc::channel();
@@ -293,25 +946,44 @@ fn main() -> Result<()> {
     for sfen_path in args.sfen_paths {
         let engine_path = args.engine_path.clone();
         let engine_options = args.engine_options.clone();
+        let search_limits = args.search_limits;
+        let options = args.options.clone();
         let progress = progress.clone();
         let tx = tx.clone();
         thread_pool.execute(move || {
-            let solve_stats =
-                solve(&engine_path, &engine_options, &sfen_path, &progress).unwrap_or_default();
+            let solve_stats = solve(
+                &engine_path,
+                &engine_options,
+                &search_limits,
+                &options,
+                &sfen_path,
+                &progress,
+            )
+            .unwrap_or_default();
             tx.send((sfen_path, solve_stats)).unwrap();
         });
     }
 
     drop(tx);
-    let mut total_nodes = 0;
+    let mut results = Vec::new();
     for (sfen_path, solve_stats) in rx.iter() {
-        total_nodes += solve_stats.nodes;
-        progress.suspend(|| print_stats(&sfen_path, &solve_stats));
+        if args.format == OutputFormat::Text {
+            progress.suspend(|| print_stats(&sfen_path, &solve_stats));
+        }
+        results.push((sfen_path, solve_stats));
     }
 
     thread_pool.join();
     progress.clear()?;
-    println!("Total nodes: {}", total_nodes);
+
+    match args.format {
+        OutputFormat::Text => {
+            let total_nodes: usize = results.iter().map(|(_, s)| s.nodes).sum();
+            println!("Total nodes: {}", total_nodes);
+        }
+        OutputFormat::Json => print_report_json(&Report::new(&results))?,
+        OutputFormat::Csv => print_report_csv(&Report::new(&results)),
+    }
 
     Ok(())
 }